@@ -0,0 +1,212 @@
+use math::{Scalar, Vector3};
+
+use crate::indexes::vertex_index::PtId;
+
+use super::{
+    index::GeoIndex,
+    seg::{SegRef, SegmentDir},
+};
+
+/// Control points of a cubic Bézier curve riding alongside a straight `Rib`.
+///
+/// `from`/`to` are the same two endpoints the rib already stores; `c1`/`c2`
+/// are the two interior control points. Keeping this separate from `Rib`
+/// lets every existing straight-chord rib stay exactly as cheap as before,
+/// while curved ribs opt in to the extra payload.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct BezierControlPoints<S: Scalar> {
+    pub(crate) c1: Vector3<S>,
+    pub(crate) c2: Vector3<S>,
+}
+
+impl<'i, S: Scalar> SegRef<'i, S> {
+    /// Flatten this segment's curve (if it has one) into a polyline, interning
+    /// the intermediate split points as new vertices in `GeoIndex.vertices`.
+    /// Returns the polyline's `PtId`s in order from `from_pt()` to `to_pt()`;
+    /// no ribs or `Seg`s are created for its pieces. A straight rib flattens
+    /// to its two endpoints.
+    ///
+    /// Uses recursive de Casteljau subdivision: flatness is tested by the
+    /// maximum perpendicular distance of the two interior control points to
+    /// the chord from `from()` to `to()`; below `tolerance` the chord is
+    /// emitted as-is, otherwise the curve is split at `t = 0.5` and both
+    /// halves are flattened recursively.
+    pub(crate) fn flatten(&self, tolerance: S) -> Vec<PtId> {
+        let Some(curve) = self.index.curves.get(&self.rib_id) else {
+            return vec![self.from_pt(), self.to_pt()];
+        };
+
+        let from = self.from();
+        let to = self.to();
+        let (c1, c2) = match self.dir {
+            SegmentDir::Fow => (curve.c1, curve.c2),
+            SegmentDir::Rev => (curve.c2, curve.c1),
+        };
+        let points = flatten_cubic(from, c1, c2, to, tolerance);
+
+        let mut pt_ids = vec![self.from_pt()];
+        for p in &points[1..points.len() - 1] {
+            pt_ids.push(self.index.vertices.get_or_insert_point(*p));
+        }
+        pt_ids.push(self.to_pt());
+        pt_ids
+    }
+}
+
+/// Perpendicular distance of `p` to the infinite line through `from -> to`.
+fn perpendicular_distance<S: Scalar>(p: Vector3<S>, from: Vector3<S>, to: Vector3<S>) -> S {
+    let chord = to - from;
+    let len_sq = chord.magnitude_squared();
+    if len_sq.is_zero() {
+        return (p - from).magnitude();
+    }
+    let v = p - from;
+    let cross = Vector3::new(
+        chord.y * v.z - chord.z * v.y,
+        chord.z * v.x - chord.x * v.z,
+        chord.x * v.y - chord.y * v.x,
+    );
+    (cross.magnitude_squared() / len_sq).sqrt()
+}
+
+fn lerp<S: Scalar>(a: Vector3<S>, b: Vector3<S>, t: S) -> Vector3<S> {
+    a + (b - a) * t
+}
+
+/// De Casteljau split of a cubic Bézier at `t = 0.5`, returning the two
+/// resulting sub-curves as `(p0, c1, c2, p3)` tuples.
+fn split_cubic<S: Scalar>(
+    p0: Vector3<S>,
+    p1: Vector3<S>,
+    p2: Vector3<S>,
+    p3: Vector3<S>,
+) -> ((Vector3<S>, Vector3<S>, Vector3<S>, Vector3<S>), (Vector3<S>, Vector3<S>, Vector3<S>, Vector3<S>)) {
+    let half = S::from_value(0.5);
+    let p01 = lerp(p0, p1, half);
+    let p12 = lerp(p1, p2, half);
+    let p23 = lerp(p2, p3, half);
+    let p012 = lerp(p01, p12, half);
+    let p123 = lerp(p12, p23, half);
+    let mid = lerp(p012, p123, half);
+    ((p0, p01, p012, mid), (mid, p123, p23, p3))
+}
+
+/// Subdivision depth past which a curve is flattened unconditionally, even
+/// if the flatness test hasn't passed. Guards against unbounded recursion
+/// for a zero (or otherwise unsatisfiable) `tolerance`; 24 levels already
+/// gives over 16 million segments, far finer than any mesh needs.
+const MAX_FLATTEN_DEPTH: u32 = 24;
+
+/// Recursively flatten a cubic Bézier into a polyline, returning the ordered
+/// list of points from `p0` to `p3` inclusive (no duplicate points at the
+/// shared subdivision boundary).
+fn flatten_cubic<S: Scalar>(
+    p0: Vector3<S>,
+    p1: Vector3<S>,
+    p2: Vector3<S>,
+    p3: Vector3<S>,
+    tolerance: S,
+) -> Vec<Vector3<S>> {
+    flatten_cubic_rec(p0, p1, p2, p3, tolerance, MAX_FLATTEN_DEPTH)
+}
+
+fn flatten_cubic_rec<S: Scalar>(
+    p0: Vector3<S>,
+    p1: Vector3<S>,
+    p2: Vector3<S>,
+    p3: Vector3<S>,
+    tolerance: S,
+    depth_remaining: u32,
+) -> Vec<Vector3<S>> {
+    let d1 = perpendicular_distance(p1, p0, p3);
+    let d2 = perpendicular_distance(p2, p0, p3);
+    if d1.max(d2) <= tolerance || depth_remaining == 0 {
+        return vec![p0, p3];
+    }
+
+    let (left, right) = split_cubic(p0, p1, p2, p3);
+    let mut points = flatten_cubic_rec(left.0, left.1, left.2, left.3, tolerance, depth_remaining - 1);
+    points.pop();
+    points.extend(flatten_cubic_rec(
+        right.0,
+        right.1,
+        right.2,
+        right.3,
+        tolerance,
+        depth_remaining - 1,
+    ));
+    points
+}
+
+impl<S: Scalar> GeoIndex<S> {
+    /// Attach a cubic Bézier curve to an already-existing straight rib, so
+    /// subsequent `flatten` calls tessellate it instead of treating it as a
+    /// chord. Boolean/intersection code keeps operating on the flattened
+    /// straight `Seg`s, never on the curve directly.
+    pub(crate) fn set_rib_curve(
+        &mut self,
+        rib_id: super::rib::RibId,
+        c1: Vector3<S>,
+        c2: Vector3<S>,
+    ) {
+        self.curves.insert(rib_id, BezierControlPoints { c1, c2 });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straight_curve_flattens_to_its_endpoints() {
+        // Control points sitting exactly on the chord: already flat.
+        let points = flatten_cubic(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(3.0, 0.0, 0.0),
+            Vector3::new(7.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+            0.001,
+        );
+        assert_eq!(points, vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(10.0, 0.0, 0.0)]);
+    }
+
+    #[test]
+    fn curved_segment_subdivides_until_flat() {
+        let points = flatten_cubic(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 10.0, 0.0),
+            Vector3::new(10.0, 10.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+            0.01,
+        );
+        assert!(points.len() > 2);
+        assert_eq!(points.first(), Some(&Vector3::new(0.0, 0.0, 0.0)));
+        assert_eq!(points.last(), Some(&Vector3::new(10.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn zero_tolerance_terminates_via_depth_cap_instead_of_overflowing() {
+        let points = flatten_cubic_rec(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 10.0, 0.0),
+            Vector3::new(10.0, 10.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+            0.0,
+            3,
+        );
+        // With only 3 levels of subdivision allowed, at most 2^3 chords come out.
+        assert!(points.len() <= 9);
+        assert!(points.len() > 1);
+    }
+
+    #[test]
+    fn perpendicular_distance_is_zero_for_a_point_on_the_line() {
+        let d = perpendicular_distance(
+            Vector3::new(5.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+        );
+        assert!(d.abs() < 1e-9);
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+
+use math::{Scalar, Vector3};
+
+use crate::indexes::vertex_index::{PtId, VertexIndex};
+
+use super::{
+    curve::BezierControlPoints,
+    rib::{Rib, RibId},
+    spatial_index::RibSpatialIndex,
+};
+
+/// The geometry index: interned vertices plus the ribs built from them,
+/// along with the broad-phase spatial index kept in sync as ribs are
+/// inserted, removed, or have an endpoint moved. Curved ribs additionally
+/// carry a `BezierControlPoints` entry in `curves`; a rib with no entry is
+/// a straight chord.
+pub struct GeoIndex<S: Scalar> {
+    pub(crate) vertices: VertexIndex<S>,
+    pub(crate) ribs: BTreeMap<RibId, Rib>,
+    pub(crate) curves: BTreeMap<RibId, BezierControlPoints<S>>,
+    pub(crate) spatial_index: RibSpatialIndex<S>,
+}
+
+impl<S: Scalar> GeoIndex<S> {
+    pub fn new() -> Self {
+        Self {
+            vertices: VertexIndex::new(),
+            ribs: BTreeMap::new(),
+            curves: BTreeMap::new(),
+            spatial_index: RibSpatialIndex::empty(),
+        }
+    }
+
+    /// Rebuild a `GeoIndex` from its authoritative parts, e.g. after loading
+    /// a persisted wire form. The spatial index isn't part of the persisted
+    /// state; callers are expected to call `rebuild_spatial_index` themselves
+    /// once the index is back in hand.
+    pub(crate) fn from_parts(
+        vertices: BTreeMap<PtId, Vector3<S>>,
+        ribs: BTreeMap<RibId, Rib>,
+        curves: BTreeMap<RibId, BezierControlPoints<S>>,
+    ) -> Self {
+        Self {
+            vertices: VertexIndex::from_points(vertices),
+            ribs,
+            curves,
+            spatial_index: RibSpatialIndex::empty(),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use uuid::Uuid;
+
+use math::{Scalar, Vector3};
+
+use crate::indexes::vertex_index::PtId;
+
+use super::index::GeoIndex;
+
+// `RibId` wraps a `Uuid` the same way `SegId` does, so it derives straight
+// through to it and a round trip preserves the original id.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RibId(Uuid);
+
+impl Default for RibId {
+    fn default() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+/// A straight chord between two vertices: `.0` is `from`, `.1` is `to`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rib(pub(crate) PtId, pub(crate) PtId);
+
+impl<S: Scalar> GeoIndex<S> {
+    /// Insert a new rib between two already-interned vertices, keeping the
+    /// spatial index in sync so `ribs_near_point`/`candidate_rib_pairs` never
+    /// go stale after a mutation.
+    pub fn insert_rib(&mut self, from: PtId, to: PtId) -> RibId {
+        let rib_id = RibId::default();
+        self.ribs.insert(rib_id, Rib(from, to));
+        self.insert_rib_into_spatial_index(rib_id);
+        rib_id
+    }
+
+    /// Remove a rib, keeping the spatial index in sync.
+    pub fn remove_rib(&mut self, rib_id: RibId) {
+        self.ribs.remove(&rib_id);
+        self.remove_rib_from_spatial_index(rib_id);
+    }
+
+    /// Move a vertex to a new position, invalidating the spatial index
+    /// entries of every rib that uses it so their bounding boxes get
+    /// recomputed instead of silently going stale.
+    pub fn move_vertex(&mut self, pt: PtId, new_position: Vector3<S>) {
+        self.vertices.set_point(pt, new_position);
+        self.invalidate_spatial_index_for_vertex(pt);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rib_id_json_round_trip_preserves_the_uuid() {
+        let rib_id = RibId::default();
+        let json = serde_json::to_string(&rib_id).unwrap();
+        let back: RibId = serde_json::from_str(&json).unwrap();
+        assert_eq!(rib_id, back);
+    }
+
+    #[test]
+    fn rib_id_bincode_round_trip_preserves_the_uuid() {
+        let rib_id = RibId::default();
+        let bytes = bincode::serialize(&rib_id).unwrap();
+        let back: RibId = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(rib_id, back);
+    }
+}
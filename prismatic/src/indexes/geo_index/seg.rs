@@ -14,10 +14,14 @@ use super::{
     rib::{Rib, RibId},
 };
 
+// `SegId` and `RibId` both wrap a `Uuid` and derive straight through to it,
+// so a round trip preserves the original id instead of minting a new one.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SegId(Uuid);
 
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SegmentDir {
     Fow,
     Rev,
@@ -33,6 +37,7 @@ impl SegmentDir {
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Seg {
     pub(super) rib_id: RibId,
     pub(super) dir: SegmentDir,
@@ -122,7 +127,10 @@ impl<'a, S: Scalar> SegmentRef<'a, S> {
         }
     }
 
-    pub(crate) fn get_intersection_params_seg_ref(&self, to: &SegRef<'_, S>) -> Option<(S, S)> {
+    pub(crate) fn get_intersection_params_seg_ref(
+        &self,
+        to: &SegRef<'_, S>,
+    ) -> SegmentIntersection<S> {
         let vertex_pulling = S::from_value(0.001); // one micrometer
         let vertex_pulling_sq = vertex_pulling * vertex_pulling;
 
@@ -136,7 +144,7 @@ impl<'a, S: Scalar> SegmentRef<'a, S> {
         let b = -Vector2::new(q.dot(&self_dir), q.dot(&segment_dir));
 
         if m.determinant().abs() < vertex_pulling_sq {
-            return None;
+            return collinear_overlap(self.from(), self.to(), to.from(), to.to(), vertex_pulling);
         }
 
         if let Some(mi) = m.try_inverse() {
@@ -145,16 +153,95 @@ impl<'a, S: Scalar> SegmentRef<'a, S> {
             let p2 = to.dir().normalize() * st.y + to.from();
             let dist = p1 - p2;
             if dist.magnitude_squared() < vertex_pulling_sq {
-                Some((st.x, st.y / to.dir().magnitude()))
+                SegmentIntersection::Point(st.x / self.dir().magnitude(), st.y / to.dir().magnitude())
             } else {
-                None
+                SegmentIntersection::NoIntersection
             }
         } else {
-            None
+            SegmentIntersection::NoIntersection
         }
     }
 }
 
+/// Handle the degenerate case of `get_intersection_params_seg_ref`: two
+/// segments whose directions are parallel. Parallel segments are only a
+/// true non-intersection when they also lie on different lines; when
+/// coplanar faces share an edge it is extremely common for them to be
+/// collinear and overlapping, so that sub-case is resolved into a 1-D
+/// interval intersection instead of being discarded.
+///
+/// Takes raw endpoints rather than `SegRef`s so the interval math can be
+/// exercised directly in tests without needing a `GeoIndex` to back it.
+fn collinear_overlap<S: Scalar>(
+    self_from: Vector3<S>,
+    self_to: Vector3<S>,
+    other_from: Vector3<S>,
+    other_to: Vector3<S>,
+    vertex_pulling: S,
+) -> SegmentIntersection<S> {
+    let self_dir = (self_to - self_from).normalize();
+    let q = self_from - other_from;
+
+    // Component of `q` perpendicular to the shared direction: zero iff
+    // the two lines (not just directions) coincide.
+    let q_perp = q - self_dir * q.dot(&self_dir);
+    if q_perp.magnitude() >= vertex_pulling {
+        return SegmentIntersection::NoIntersection;
+    }
+
+    // Project every endpoint onto the shared axis, with `self_from` as
+    // origin, so both segments' extents become 1-D intervals.
+    let self_len = (self_to - self_from).magnitude();
+    let other_from_param = -q.dot(&self_dir);
+    let other_to_param = (other_to - self_from).dot(&self_dir);
+
+    let self_lo = S::zero();
+    let self_hi = self_len;
+    let (other_lo, other_hi) = if other_from_param <= other_to_param {
+        (other_from_param, other_to_param)
+    } else {
+        (other_to_param, other_from_param)
+    };
+
+    let overlap_lo = if self_lo > other_lo { self_lo } else { other_lo };
+    let overlap_hi = if self_hi < other_hi { self_hi } else { other_hi };
+
+    if overlap_hi < overlap_lo - vertex_pulling {
+        return SegmentIntersection::NoIntersection;
+    }
+
+    let self_param = |v: S| v / self_len;
+    let other_param = |v: S| (v - other_from_param) / (other_to_param - other_from_param);
+
+    if overlap_hi - overlap_lo <= vertex_pulling {
+        let mid = (overlap_lo + overlap_hi) / S::from_value(2);
+        return SegmentIntersection::Point(self_param(mid), other_param(mid));
+    }
+
+    SegmentIntersection::Overlap {
+        self_range: (self_param(overlap_lo), self_param(overlap_hi)),
+        other_range: (other_param(overlap_lo), other_param(overlap_hi)),
+    }
+}
+
+/// Result of intersecting two segments.
+///
+/// Parallel, collinear, overlapping segments are a common degeneracy in
+/// Boolean mesh operations (two coplanar faces sharing an edge), so unlike a
+/// plain `Option<(S, S)>` this distinguishes a genuine miss from a single
+/// touching point from a full 1-D overlap range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SegmentIntersection<S> {
+    NoIntersection,
+    /// Segments cross (or touch) at a single point; parameters are
+    /// fractions in `[0, 1]` along `self` and `to` respectively.
+    Point(S, S),
+    /// Segments are collinear and their extents overlap over a range;
+    /// each range is a pair of fractions in `[0, 1]` along that segment,
+    /// in the order that corresponds to the other segment's range.
+    Overlap { self_range: (S, S), other_range: (S, S) },
+}
+
 impl<S: Scalar> SegRef<'_, S> {
     pub fn from(&self) -> Vector3<S> {
         self.index.vertices.get_point(self.from_pt())
@@ -300,3 +387,98 @@ impl<'a, S: Scalar + 'a> GeoObject<'a, S> for Seg {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collinear_overlap_partial() {
+        // self: (0,0,0)->(10,0,0), other: (5,0,0)->(15,0,0)
+        let got = collinear_overlap(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+            Vector3::new(5.0, 0.0, 0.0),
+            Vector3::new(15.0, 0.0, 0.0),
+            0.001,
+        );
+        match got {
+            SegmentIntersection::Overlap { self_range, other_range } => {
+                assert!((self_range.0 - 0.5).abs() < 1e-9);
+                assert!((self_range.1 - 1.0).abs() < 1e-9);
+                assert!((other_range.0 - 0.0).abs() < 1e-9);
+                assert!((other_range.1 - 0.5).abs() < 1e-9);
+            }
+            other => panic!("expected Overlap, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn collinear_overlap_touching_is_a_point() {
+        // self: (0,0,0)->(10,0,0), other: (10,0,0)->(20,0,0)
+        let got = collinear_overlap(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+            Vector3::new(20.0, 0.0, 0.0),
+            0.001,
+        );
+        match got {
+            SegmentIntersection::Point(s, t) => {
+                assert!((s - 1.0).abs() < 1e-9);
+                assert!((t - 0.0).abs() < 1e-9);
+            }
+            other => panic!("expected Point, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn collinear_no_overlap_when_disjoint() {
+        // self: (0,0,0)->(10,0,0), other: (11,0,0)->(20,0,0) - a clear gap
+        let got = collinear_overlap(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+            Vector3::new(11.0, 0.0, 0.0),
+            Vector3::new(20.0, 0.0, 0.0),
+            0.001,
+        );
+        assert_eq!(got, SegmentIntersection::NoIntersection);
+    }
+
+    #[test]
+    fn parallel_but_not_collinear_is_no_intersection() {
+        // self on the X axis, other parallel to it but offset in Y
+        let got = collinear_overlap(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(10.0, 1.0, 0.0),
+            0.001,
+        );
+        assert_eq!(got, SegmentIntersection::NoIntersection);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn seg_id_json_round_trip_preserves_the_uuid() {
+        let seg_id = SegId::default();
+        let json = serde_json::to_string(&seg_id).unwrap();
+        let back: SegId = serde_json::from_str(&json).unwrap();
+        assert_eq!(seg_id, back);
+    }
+
+    #[test]
+    fn seg_bincode_round_trip_preserves_rib_id_and_direction() {
+        let seg = Seg {
+            rib_id: RibId::default(),
+            dir: SegmentDir::Rev,
+        };
+        let bytes = bincode::serialize(&seg).unwrap();
+        let back: Seg = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(seg, back);
+    }
+}
@@ -0,0 +1,72 @@
+//! Optional on-disk persistence for `GeoIndex`, gated behind the `serde`
+//! feature so consumers that never need it don't pay for the dependency.
+//! Supports both JSON (for debugging/inspection) and `bincode` (for fast
+//! reload). `SegId`/`RibId` wrap a `Uuid` and round-trip through it
+//! directly, so ids are preserved rather than regenerated and cross
+//! references between ribs and segments stay valid after reload.
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use math::{Scalar, Vector3};
+
+use crate::indexes::vertex_index::PtId;
+
+use super::{
+    curve::BezierControlPoints,
+    index::GeoIndex,
+    rib::{Rib, RibId},
+};
+
+/// Wire form of a `GeoIndex`: just the authoritative vertex/rib/curve data.
+/// The spatial index is intentionally left out and rebuilt after load, the
+/// same way any other derived cache would be.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+struct GeoIndexWire<S: Scalar> {
+    vertices: BTreeMap<PtId, Vector3<S>>,
+    ribs: BTreeMap<RibId, Rib>,
+    curves: BTreeMap<RibId, BezierControlPoints<S>>,
+}
+
+#[derive(Debug)]
+pub enum PersistError {
+    Json(serde_json::Error),
+    Bincode(bincode::Error),
+}
+
+impl<S: Scalar + Serialize + for<'de> Deserialize<'de>> GeoIndex<S> {
+    fn to_wire(&self) -> GeoIndexWire<S> {
+        GeoIndexWire {
+            vertices: self.vertices.points_by_id(),
+            ribs: self.ribs.clone(),
+            curves: self.curves.clone(),
+        }
+    }
+
+    fn from_wire(wire: GeoIndexWire<S>) -> Self {
+        let mut index = GeoIndex::from_parts(wire.vertices, wire.ribs, wire.curves);
+        index.rebuild_spatial_index();
+        index
+    }
+
+    pub fn to_json(&self) -> Result<String, PersistError> {
+        serde_json::to_string(&self.to_wire()).map_err(PersistError::Json)
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, PersistError> {
+        let wire: GeoIndexWire<S> = serde_json::from_str(data).map_err(PersistError::Json)?;
+        Ok(Self::from_wire(wire))
+    }
+
+    pub fn to_bincode(&self) -> Result<Vec<u8>, PersistError> {
+        bincode::serialize(&self.to_wire()).map_err(PersistError::Bincode)
+    }
+
+    pub fn from_bincode(data: &[u8]) -> Result<Self, PersistError> {
+        let wire: GeoIndexWire<S> = bincode::deserialize(data).map_err(PersistError::Bincode)?;
+        Ok(Self::from_wire(wire))
+    }
+}
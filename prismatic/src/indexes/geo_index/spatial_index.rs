@@ -0,0 +1,440 @@
+use math::{Scalar, Vector3};
+
+use crate::indexes::vertex_index::PtId;
+
+use super::{index::GeoIndex, rib::RibId};
+
+/// Axis-aligned bounding box, padded by a small epsilon so that ribs whose
+/// exact geometry only just touches still show up as overlapping candidates.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Aabb<S: Scalar> {
+    pub(crate) min: Vector3<S>,
+    pub(crate) max: Vector3<S>,
+}
+
+impl<S: Scalar> Aabb<S> {
+    fn from_points(a: Vector3<S>, b: Vector3<S>) -> Self {
+        Self {
+            min: Vector3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Vector3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    fn padded(self, eps: S) -> Self {
+        let e = Vector3::new(eps, eps, eps);
+        Self {
+            min: self.min - e,
+            max: self.max + e,
+        }
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self {
+            min: Vector3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            max: Vector3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        }
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+
+    /// Longest axis, used to pick a split plane when bulk-loading.
+    fn longest_axis(&self) -> usize {
+        let d = self.max - self.min;
+        if d.x >= d.y && d.x >= d.z {
+            0
+        } else if d.y >= d.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    fn axis(v: Vector3<S>, axis: usize) -> S {
+        match axis {
+            0 => v.x,
+            1 => v.y,
+            _ => v.z,
+        }
+    }
+}
+
+/// A bulk-loadable, incrementally-updatable AABB-BVH over rib bounding boxes.
+///
+/// This is the broad-phase companion to the exact pairwise math in `seg.rs`:
+/// `ribs_near_point` and `candidate_rib_pairs` narrow a whole-index query down
+/// to the short list of ribs whose padded boxes actually overlap, so the
+/// exact intersection/distance routines only ever run on real candidates.
+pub(crate) enum RibBvh<S: Scalar> {
+    Leaf {
+        rib_id: RibId,
+        bbox: Aabb<S>,
+    },
+    Node {
+        bbox: Aabb<S>,
+        left: Box<RibBvh<S>>,
+        right: Box<RibBvh<S>>,
+    },
+}
+
+impl<S: Scalar> RibBvh<S> {
+    fn bbox(&self) -> Aabb<S> {
+        match self {
+            RibBvh::Leaf { bbox, .. } => *bbox,
+            RibBvh::Node { bbox, .. } => *bbox,
+        }
+    }
+
+    /// Bulk-load a balanced tree from scratch via recursive median splits on
+    /// the longest axis of the enclosing box.
+    fn bulk_load(mut items: Vec<(RibId, Aabb<S>)>) -> Option<Self> {
+        if items.is_empty() {
+            return None;
+        }
+        if items.len() == 1 {
+            let (rib_id, bbox) = items.pop().unwrap();
+            return Some(RibBvh::Leaf { rib_id, bbox });
+        }
+
+        let enclosing = items
+            .iter()
+            .map(|(_, b)| *b)
+            .reduce(Aabb::union)
+            .expect("non-empty items");
+        let axis = enclosing.longest_axis();
+        items.sort_by(|(_, a), (_, b)| {
+            let ca = Aabb::axis(a.min, axis) + Aabb::axis(a.max, axis);
+            let cb = Aabb::axis(b.min, axis) + Aabb::axis(b.max, axis);
+            ca.partial_cmp(&cb).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mid = items.len() / 2;
+        let right_items = items.split_off(mid);
+        let left = Self::bulk_load(items)?;
+        let right = Self::bulk_load(right_items)?;
+        Some(RibBvh::Node {
+            bbox: left.bbox().union(right.bbox()),
+            left: Box::new(left),
+            right: Box::new(right),
+        })
+    }
+
+    /// Insert a single rib, growing whichever child's box would expand least.
+    /// Not rebalanced on the fly; callers that insert many ribs in a row
+    /// should prefer a full `rebuild` once they are done.
+    fn insert(self, rib_id: RibId, bbox: Aabb<S>) -> Self {
+        match self {
+            RibBvh::Leaf {
+                rib_id: other_id,
+                bbox: other_bbox,
+            } => RibBvh::Node {
+                bbox: bbox.union(other_bbox),
+                left: Box::new(RibBvh::Leaf {
+                    rib_id: other_id,
+                    bbox: other_bbox,
+                }),
+                right: Box::new(RibBvh::Leaf { rib_id, bbox }),
+            },
+            RibBvh::Node {
+                bbox: node_bbox,
+                left,
+                right,
+            } => {
+                let left_grown = left.bbox().union(bbox);
+                let right_grown = right.bbox().union(bbox);
+                let left_cost = Self::volume(left_grown) - Self::volume(left.bbox());
+                let right_cost = Self::volume(right_grown) - Self::volume(right.bbox());
+                if left_cost <= right_cost {
+                    RibBvh::Node {
+                        bbox: node_bbox.union(bbox),
+                        left: Box::new(left.insert(rib_id, bbox)),
+                        right,
+                    }
+                } else {
+                    RibBvh::Node {
+                        bbox: node_bbox.union(bbox),
+                        left,
+                        right: Box::new(right.insert(rib_id, bbox)),
+                    }
+                }
+            }
+        }
+    }
+
+    fn volume(bbox: Aabb<S>) -> S {
+        let d = bbox.max - bbox.min;
+        d.x * d.y + d.y * d.z + d.z * d.x
+    }
+
+    /// Remove a rib by id, returning the updated subtree (`None` if it became
+    /// empty). Collapses a node to its surviving child so the tree never
+    /// carries dead leaves around.
+    fn remove(self, rib_id: RibId) -> Option<Self> {
+        match self {
+            RibBvh::Leaf { rib_id: id, .. } if id == rib_id => None,
+            RibBvh::Leaf { .. } => Some(self),
+            RibBvh::Node { left, right, .. } => {
+                let left = left.remove(rib_id);
+                let right = right.remove(rib_id);
+                match (left, right) {
+                    (Some(left), Some(right)) => Some(RibBvh::Node {
+                        bbox: left.bbox().union(right.bbox()),
+                        left: Box::new(left),
+                        right: Box::new(right),
+                    }),
+                    (Some(only), None) | (None, Some(only)) => Some(only),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+
+    fn query(&self, target: &Aabb<S>, out: &mut Vec<RibId>) {
+        if !self.bbox().intersects(target) {
+            return;
+        }
+        match self {
+            RibBvh::Leaf { rib_id, .. } => out.push(*rib_id),
+            RibBvh::Node { left, right, .. } => {
+                left.query(target, out);
+                right.query(target, out);
+            }
+        }
+    }
+
+    fn collect_overlapping_pairs(&self, out: &mut Vec<(RibId, RibId)>) {
+        if let RibBvh::Node { left, right, .. } = self {
+            left.collect_overlapping_pairs(out);
+            right.collect_overlapping_pairs(out);
+            left.cross_pairs(right, out);
+        }
+    }
+
+    fn cross_pairs(&self, other: &Self, out: &mut Vec<(RibId, RibId)>) {
+        if !self.bbox().intersects(&other.bbox()) {
+            return;
+        }
+        match (self, other) {
+            (RibBvh::Leaf { rib_id: a, .. }, RibBvh::Leaf { rib_id: b, .. }) => {
+                out.push((*a, *b));
+            }
+            (RibBvh::Leaf { .. }, RibBvh::Node { left, right, .. }) => {
+                self.cross_pairs(left, out);
+                self.cross_pairs(right, out);
+            }
+            (RibBvh::Node { left, right, .. }, RibBvh::Leaf { .. }) => {
+                left.cross_pairs(other, out);
+                right.cross_pairs(other, out);
+            }
+            (
+                RibBvh::Node {
+                    left: a_left,
+                    right: a_right,
+                    ..
+                },
+                RibBvh::Node { .. },
+            ) => {
+                a_left.cross_pairs(other, out);
+                a_right.cross_pairs(other, out);
+            }
+        }
+    }
+}
+
+/// Spatial acceleration structure for the ribs of a single `GeoIndex`.
+///
+/// `GeoIndex` keeps one of these alongside its vertex/rib maps and keeps it
+/// up to date as ribs are inserted or removed, or as vertices move.
+pub(crate) struct RibSpatialIndex<S: Scalar> {
+    root: Option<RibBvh<S>>,
+}
+
+impl<S: Scalar> RibSpatialIndex<S> {
+    pub(crate) fn empty() -> Self {
+        Self { root: None }
+    }
+
+    pub(crate) fn rebuild(items: Vec<(RibId, Aabb<S>)>) -> Self {
+        Self {
+            root: RibBvh::bulk_load(items),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, rib_id: RibId, bbox: Aabb<S>) {
+        self.root = Some(match self.root.take() {
+            Some(root) => root.insert(rib_id, bbox),
+            None => RibBvh::Leaf { rib_id, bbox },
+        });
+    }
+
+    pub(crate) fn remove(&mut self, rib_id: RibId) {
+        self.root = self.root.take().and_then(|root| root.remove(rib_id));
+    }
+
+    fn query(&self, target: Aabb<S>) -> Vec<RibId> {
+        let mut out = Vec::new();
+        if let Some(root) = &self.root {
+            root.query(&target, &mut out);
+        }
+        out
+    }
+
+    fn overlapping_pairs(&self) -> Vec<(RibId, RibId)> {
+        let mut out = Vec::new();
+        if let Some(root) = &self.root {
+            root.collect_overlapping_pairs(&mut out);
+        }
+        out
+    }
+}
+
+impl<S: Scalar> GeoIndex<S> {
+    /// Padded AABB of a rib, used both to populate the spatial index and to
+    /// query it; padding matches the `vertex_pulling` tolerance used by the
+    /// exact intersection math in `seg.rs`.
+    pub(crate) fn rib_bbox(&self, rib_id: RibId) -> Aabb<S> {
+        let vertex_pulling = S::from_value(0.001);
+        let rib = self.ribs[&rib_id];
+        let from = self.vertices.get_point(rib.0);
+        let to = self.vertices.get_point(rib.1);
+        Aabb::from_points(from, to).padded(vertex_pulling)
+    }
+
+    /// Rebuild the spatial index from every rib currently in the index. Call
+    /// after a batch of inserts/removals instead of relying on the cheaper
+    /// but unbalanced incremental `insert`/`remove`.
+    pub(crate) fn rebuild_spatial_index(&mut self) {
+        let items = self
+            .ribs
+            .keys()
+            .map(|&rib_id| (rib_id, self.rib_bbox(rib_id)))
+            .collect();
+        self.spatial_index = RibSpatialIndex::rebuild(items);
+    }
+
+    pub(crate) fn insert_rib_into_spatial_index(&mut self, rib_id: RibId) {
+        let bbox = self.rib_bbox(rib_id);
+        self.spatial_index.insert(rib_id, bbox);
+    }
+
+    pub(crate) fn remove_rib_from_spatial_index(&mut self, rib_id: RibId) {
+        self.spatial_index.remove(rib_id);
+    }
+
+    /// A moved vertex invalidates the bounding boxes of every rib that uses
+    /// it; the cheapest correct fix is to re-insert those ribs.
+    pub(crate) fn invalidate_spatial_index_for_vertex(&mut self, pt: PtId) {
+        let affected: Vec<RibId> = self
+            .ribs
+            .iter()
+            .filter(|(_, rib)| rib.0 == pt || rib.1 == pt)
+            .map(|(&rib_id, _)| rib_id)
+            .collect();
+        for rib_id in affected {
+            self.remove_rib_from_spatial_index(rib_id);
+            self.insert_rib_into_spatial_index(rib_id);
+        }
+    }
+
+    /// Broad-phase lookup: ribs whose padded bounding box lies within
+    /// `radius` of `p`. Callers still need to run the exact
+    /// `SegmentRef::distance_to_pt_squared` check against this short list.
+    pub fn ribs_near_point(&self, p: Vector3<S>, radius: S) -> Vec<RibId> {
+        let r = Vector3::new(radius, radius, radius);
+        let target = Aabb {
+            min: p - r,
+            max: p + r,
+        };
+        self.spatial_index.query(target)
+    }
+
+    /// Broad-phase lookup: every pair of ribs whose padded bounding boxes
+    /// overlap. Callers run `SegmentRef::get_intersection_params_seg_ref` only on
+    /// these pairs instead of the full O(n^2) cross product.
+    pub fn candidate_rib_pairs(&self) -> Vec<(RibId, RibId)> {
+        self.spatial_index.overlapping_pairs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aabb(min: (f64, f64, f64), max: (f64, f64, f64)) -> Aabb<f64> {
+        Aabb {
+            min: Vector3::new(min.0, min.1, min.2),
+            max: Vector3::new(max.0, max.1, max.2),
+        }
+    }
+
+    #[test]
+    fn aabb_union_covers_both_boxes() {
+        let a = aabb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0));
+        let b = aabb((2.0, -1.0, 0.5), (3.0, 0.0, 2.0));
+        let u = a.union(b);
+        assert_eq!(u.min, Vector3::new(0.0, -1.0, 0.0));
+        assert_eq!(u.max, Vector3::new(3.0, 1.0, 2.0));
+    }
+
+    #[test]
+    fn aabb_intersects_only_when_boxes_overlap() {
+        let a = aabb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0));
+        let touching = aabb((1.0, 0.0, 0.0), (2.0, 1.0, 1.0));
+        let disjoint = aabb((2.0, 0.0, 0.0), (3.0, 1.0, 1.0));
+        assert!(a.intersects(&touching));
+        assert!(!a.intersects(&disjoint));
+    }
+
+    #[test]
+    fn query_finds_only_overlapping_ribs() {
+        let near = RibId::default();
+        let far = RibId::default();
+        let index = RibSpatialIndex::rebuild(vec![
+            (near, aabb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0))),
+            (far, aabb((100.0, 100.0, 100.0), (101.0, 101.0, 101.0))),
+        ]);
+        let hits = index.query(aabb((0.5, 0.5, 0.5), (2.0, 2.0, 2.0)));
+        assert_eq!(hits, vec![near]);
+    }
+
+    #[test]
+    fn overlapping_pairs_finds_cross_box_overlaps_only() {
+        let a = RibId::default();
+        let b = RibId::default();
+        let c = RibId::default();
+        let index = RibSpatialIndex::rebuild(vec![
+            (a, aabb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0))),
+            (b, aabb((0.5, 0.5, 0.5), (1.5, 1.5, 1.5))),
+            (c, aabb((100.0, 100.0, 100.0), (101.0, 101.0, 101.0))),
+        ]);
+        let pairs = index.overlapping_pairs();
+        assert_eq!(pairs.len(), 1);
+        let (p, q) = pairs[0];
+        assert!((p == a && q == b) || (p == b && q == a));
+    }
+
+    #[test]
+    fn insert_and_remove_keep_query_in_sync() {
+        let rib_id = RibId::default();
+        let mut index = RibSpatialIndex::empty();
+        index.insert(rib_id, aabb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0)));
+        assert_eq!(index.query(aabb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0))), vec![rib_id]);
+
+        index.remove(rib_id);
+        assert!(index.query(aabb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0))).is_empty());
+    }
+}
@@ -0,0 +1,375 @@
+use math::{Scalar, Vector3};
+
+use crate::indexes::vertex_index::PtId;
+
+use super::{index::GeoIndex, seg::SegRef};
+
+/// How an open chain's two free ends are terminated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapStyle {
+    Butt,
+    Square,
+    Round,
+}
+
+/// How two consecutive segments of a chain are joined on the offset side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JoinStyle<S: Scalar> {
+    /// Intersect the two offset lines; fall back to `Bevel` past `limit`
+    /// (the ratio of miter length to half-width).
+    Miter { limit: S },
+    Bevel,
+    /// A short arc, flattened until each chord's sagitta is within `tolerance`.
+    Round { tolerance: S },
+}
+
+/// A chain of connected `Seg`s sharing `PtId`s end to end, stroked at `width`
+/// into a single closed fill outline. The points of the two offset polylines
+/// and of the cap/join geometry that connects them are interned into
+/// `GeoIndex` as `PtId`s; no ribs or `Seg`s are created for the outline's
+/// edges.
+pub(crate) struct Stroke<S: Scalar> {
+    pub(crate) width: S,
+    pub(crate) join: JoinStyle<S>,
+    pub(crate) cap: CapStyle,
+}
+
+impl<S: Scalar> GeoIndex<S> {
+    /// Stroke a chain of segments into a filled band, returning the `PtId`s
+    /// of the closed outline polygon in order. `chain` must be an ordered,
+    /// already-connected sequence (`chain[i].to_pt() == chain[i + 1].from_pt()`).
+    pub(crate) fn stroke_chain(&mut self, chain: &[SegRef<'_, S>], stroke: Stroke<S>) -> Vec<PtId> {
+        assert!(!chain.is_empty(), "a stroked chain needs at least one segment");
+        let half = stroke.width / S::from_value(2);
+        let closed = chain.first().unwrap().from_pt() == chain.last().unwrap().to_pt();
+        // Picked once for the whole chain so every segment's normal is
+        // derived from the same reference axis, instead of each segment
+        // choosing independently and risking a sign flip partway through.
+        let reference = chain_reference_axis(chain);
+
+        let left = self.offset_side(chain, half, &stroke.join, closed, half, reference);
+        let right = self.offset_side(chain, -half, &stroke.join, closed, half, reference);
+
+        if closed {
+            // The band of a closed chain is a ring: walk the left loop all the
+            // way round, bridge across at the start junction, walk the right
+            // loop backwards, and bridge back.
+            let (left_start, right_start) = (left[0], right[0]);
+            let mut outline = left;
+            outline.push(left_start);
+            outline.push(right_start);
+            outline.extend(right[1..].iter().rev());
+            outline.push(right_start);
+            outline
+        } else {
+            let mut outline = left;
+            outline.extend(self.cap_points(chain.last().unwrap(), half, stroke.cap, false, reference));
+            outline.extend(right.into_iter().rev());
+            outline.extend(self.cap_points(chain.first().unwrap(), half, stroke.cap, true, reference));
+            outline
+        }
+    }
+
+    /// Offset every segment in the chain by `signed_offset` along its normal
+    /// (left is positive, right is negative) and resolve the join at each
+    /// interior junction, returning the resulting polyline's `PtId`s.
+    fn offset_side(
+        &mut self,
+        chain: &[SegRef<'_, S>],
+        signed_offset: S,
+        join: &JoinStyle<S>,
+        closed: bool,
+        half: S,
+        reference: Vector3<S>,
+    ) -> Vec<PtId> {
+        let offsets: Vec<(Vector3<S>, Vector3<S>)> = chain
+            .iter()
+            .map(|seg| {
+                let n = normal(seg.dir(), reference) * signed_offset;
+                (seg.from() + n, seg.to() + n)
+            })
+            .collect();
+
+        // With miter or bevel joins, a closed chain has one vertex per segment
+        // (the join wraps back onto itself, so there's no separate start/end
+        // point) and an open chain has one more vertex than segments (its two
+        // free ends aren't joined); a round join contributes its arc's points.
+        let mut points = Vec::with_capacity(offsets.len() + 1);
+        if closed {
+            let center = chain[0].from();
+            points.extend(self.resolve_join(center, *offsets.last().unwrap(), offsets[0], join, half));
+        } else {
+            points.push(offsets[0].0);
+        }
+        for i in 0..offsets.len() - 1 {
+            let center = chain[i + 1].from();
+            points.extend(self.resolve_join(center, offsets[i], offsets[i + 1], join, half));
+        }
+        if !closed {
+            points.push(offsets.last().unwrap().1);
+        }
+
+        points.into_iter().map(|p| self.vertices.get_or_insert_point(p)).collect()
+    }
+
+    /// The shared point(s) at an interior junction between two consecutive
+    /// offset segments: an intersection of the two offset lines for a miter
+    /// join (falling back to the midpoint, i.e. a bevel, past the miter
+    /// limit), the midpoint outright for an explicit bevel join, or a
+    /// flattened arc around `center` for a round join. `half` is the
+    /// stroke's half-width, against which `limit` (a ratio) is scaled into
+    /// the absolute distance the miter point is checked against.
+    fn resolve_join(
+        &mut self,
+        center: Vector3<S>,
+        a: (Vector3<S>, Vector3<S>),
+        b: (Vector3<S>, Vector3<S>),
+        join: &JoinStyle<S>,
+        half: S,
+    ) -> Vec<Vector3<S>> {
+        let bevel_point = (a.1 + b.0) / S::from_value(2);
+        match join {
+            JoinStyle::Bevel => vec![bevel_point],
+            JoinStyle::Round { tolerance } => {
+                let arc = flatten_arc(a.1 - center, b.0 - center, *tolerance);
+                arc.into_iter().map(|v| center + v).collect()
+            }
+            JoinStyle::Miter { limit } => match line_intersection(a.0, a.1, b.0, b.1) {
+                Some(p) if (p - a.1).magnitude() <= *limit * half => vec![p],
+                _ => vec![bevel_point],
+            },
+        }
+    }
+
+    /// Cap geometry for one free end of an open chain, returned as the
+    /// `PtId`s to splice into the outline between the two offset polylines.
+    /// `leading` is true for the chain's start (the cap comes last in
+    /// outline order, closing back onto the left polyline's first point).
+    fn cap_points(
+        &mut self,
+        seg: &SegRef<'_, S>,
+        half: S,
+        cap: CapStyle,
+        leading: bool,
+        reference: Vector3<S>,
+    ) -> Vec<PtId> {
+        let (center, dir) = if leading {
+            (seg.from(), -seg.dir().normalize())
+        } else {
+            (seg.to(), seg.dir().normalize())
+        };
+        let n = normal(dir, reference) * half;
+
+        let points = match cap {
+            CapStyle::Butt => vec![],
+            CapStyle::Square => vec![center + dir * half + n, center + dir * half - n],
+            CapStyle::Round => flatten_semicircle(center, n, dir, half),
+        };
+        points.into_iter().map(|p| self.vertices.get_or_insert_point(p)).collect()
+    }
+}
+
+fn cross<S: Scalar>(a: Vector3<S>, b: Vector3<S>) -> Vector3<S> {
+    Vector3::new(
+        a.y * b.z - a.z * b.y,
+        a.z * b.x - a.x * b.z,
+        a.x * b.y - a.y * b.x,
+    )
+}
+
+/// Reference axis used to derive every offset normal in a chain. Picking it
+/// once from the whole chain (rather than letting each segment fall back
+/// independently) keeps the offset side consistent even when one segment
+/// happens to run parallel to the usual Z reference while its neighbors
+/// don't.
+fn chain_reference_axis<S: Scalar>(chain: &[SegRef<'_, S>]) -> Vector3<S> {
+    let z_axis = Vector3::new(S::zero(), S::zero(), S::from_value(1));
+    let is_usable = |dir: Vector3<S>| cross(dir.normalize(), z_axis).magnitude_squared() >= S::from_value(1e-12);
+    if chain.iter().any(|seg| is_usable(seg.dir())) {
+        z_axis
+    } else {
+        Vector3::new(S::from_value(1), S::zero(), S::zero())
+    }
+}
+
+/// Unit vector perpendicular to `dir`, used as the offset direction for a
+/// stroked segment. `dir` may point anywhere in 3D; `reference` is the axis
+/// to cross with, chosen once per chain by `chain_reference_axis` so every
+/// segment's normal stays on a consistent side.
+fn normal<S: Scalar>(dir: Vector3<S>, reference: Vector3<S>) -> Vector3<S> {
+    cross(dir.normalize(), reference).normalize()
+}
+
+/// Nearest-intersection point of infinite lines `p0->p1` and `p2->p3` in 3D.
+/// Returns `None` when the lines are parallel, or when the lines are skew
+/// (the closest points on each line are farther apart than a vertex-pulling
+/// tolerance), consistent with `normal` no longer assuming both lines lie in
+/// the XY plane.
+fn line_intersection<S: Scalar>(
+    p0: Vector3<S>,
+    p1: Vector3<S>,
+    p2: Vector3<S>,
+    p3: Vector3<S>,
+) -> Option<Vector3<S>> {
+    let vertex_pulling = S::from_value(0.001); // one micrometer
+    let d1 = p1 - p0;
+    let d2 = p3 - p2;
+    let r = p0 - p2;
+    let a = d1.dot(&d1);
+    let e = d2.dot(&d2);
+    let f = d2.dot(&r);
+    let c = d1.dot(&r);
+    let b = d1.dot(&d2);
+    let denom = a * e - b * b;
+    if denom.abs() < S::from_value(1e-9) {
+        return None;
+    }
+    let s = (b * f - c * e) / denom;
+    let t = (a * f - b * c) / denom;
+    let closest1 = p0 + d1 * s;
+    let closest2 = p2 + d2 * t;
+    if (closest1 - closest2).magnitude() > vertex_pulling {
+        return None;
+    }
+    Some((closest1 + closest2) / S::from_value(2))
+}
+
+/// Flatten a half-turn arc from `center + n` to `center - n` (bulging out
+/// along `dir`) into a short polyline, split finely enough that the chord
+/// error stays small relative to the stroke's half-width. Only the interior
+/// points are returned; the two ends are not included.
+fn flatten_semicircle<S: Scalar>(
+    center: Vector3<S>,
+    n: Vector3<S>,
+    dir: Vector3<S>,
+    radius: S,
+) -> Vec<Vector3<S>> {
+    const STEPS: usize = 8;
+    (1..STEPS)
+        .map(|i| {
+            let t = S::from_value(i as f64 / STEPS as f64);
+            let angle = t * S::pi();
+            let (s, c) = angle.sin_cos();
+            center + dir * (radius * s) + n * c
+        })
+        .collect()
+}
+
+/// Subdivision depth past which a join arc is flattened unconditionally,
+/// mirroring `MAX_FLATTEN_DEPTH` in `curve.rs`.
+const MAX_ARC_DEPTH: u32 = 16;
+
+/// Flatten the arc from `v0` to `v1` (both measured from the arc's center,
+/// with equal magnitude) into a polyline via recursive midpoint projection:
+/// the chord midpoint is pushed out onto the circle and used to split the
+/// arc in two, recursing until the sagitta (the gap between the chord
+/// midpoint and the projected point) is within `tolerance`. Returns the
+/// endpoints `v0`/`v1` inclusive.
+fn flatten_arc<S: Scalar>(v0: Vector3<S>, v1: Vector3<S>, tolerance: S) -> Vec<Vector3<S>> {
+    flatten_arc_rec(v0, v1, tolerance, MAX_ARC_DEPTH)
+}
+
+fn flatten_arc_rec<S: Scalar>(
+    v0: Vector3<S>,
+    v1: Vector3<S>,
+    tolerance: S,
+    depth_remaining: u32,
+) -> Vec<Vector3<S>> {
+    let radius = v0.magnitude();
+    let chord_mid = (v0 + v1) / S::from_value(2);
+    let arc_mid = if chord_mid.magnitude_squared().is_zero() {
+        // v0 and v1 point in opposite directions: any perpendicular is a
+        // valid midpoint of the (half-turn) arc between them.
+        normal(v0, Vector3::new(S::zero(), S::zero(), S::from_value(1))) * radius
+    } else {
+        chord_mid.normalize() * radius
+    };
+    let sagitta = (arc_mid - chord_mid).magnitude();
+    if sagitta <= tolerance || depth_remaining == 0 {
+        return vec![v0, v1];
+    }
+
+    let mut points = flatten_arc_rec(v0, arc_mid, tolerance, depth_remaining - 1);
+    points.pop();
+    points.extend(flatten_arc_rec(arc_mid, v1, tolerance, depth_remaining - 1));
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn z_axis() -> Vector3<f64> {
+        Vector3::new(0.0, 0.0, 1.0)
+    }
+
+    #[test]
+    fn normal_of_xy_direction_is_unit_length_and_perpendicular() {
+        let n = normal(Vector3::new(1.0, 0.0, 0.0), z_axis());
+        assert!((n.magnitude() - 1.0).abs() < 1e-9);
+        assert!(n.dot(&Vector3::new(1.0, 0.0, 0.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn normal_of_a_direction_with_z_component_stays_unit_length() {
+        // A direction tilted out of the XY plane used to produce a normal
+        // shorter than one (or zero when `dir` pointed straight along Z).
+        let n = normal(Vector3::new(1.0, 0.0, 1.0), z_axis());
+        assert!((n.magnitude() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn normal_of_vertical_direction_uses_the_fallback_reference() {
+        let n = normal(Vector3::new(0.0, 0.0, 1.0), Vector3::new(1.0, 0.0, 0.0));
+        assert!((n.magnitude() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn chain_reference_axis_falls_back_when_every_segment_is_vertical() {
+        let mut index = GeoIndex::<f64>::new();
+        let bottom = index.vertices.get_or_insert_point(Vector3::new(0.0, 0.0, 0.0));
+        let top = index.vertices.get_or_insert_point(Vector3::new(0.0, 0.0, 1.0));
+        let rib_id = index.insert_rib(bottom, top);
+        let seg = super::super::seg::Seg {
+            rib_id,
+            dir: super::super::seg::SegmentDir::Fow,
+        }
+        .to_ref(&index);
+        let axis = chain_reference_axis(&[seg]);
+        assert_eq!(axis, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn flatten_arc_subdivides_a_quarter_turn_until_flat() {
+        let points = flatten_arc(Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), 0.01);
+        assert!(points.len() > 2);
+        assert_eq!(points.first(), Some(&Vector3::new(1.0, 0.0, 0.0)));
+        assert_eq!(points.last(), Some(&Vector3::new(0.0, 1.0, 0.0)));
+        for p in &points {
+            assert!((p.magnitude() - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn line_intersection_finds_the_crossing_point() {
+        let p = line_intersection(
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        )
+        .expect("lines cross at the origin");
+        assert!(p.magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn line_intersection_returns_none_for_parallel_lines() {
+        let p = line_intersection(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        );
+        assert!(p.is_none());
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+
+use math::{Scalar, Vector3};
+
+// `PtId` wraps a `Uuid` the same way `SegId`/`RibId` do, so it derives
+// straight through to it and a round trip preserves the original id.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PtId(Uuid);
+
+impl Default for PtId {
+    fn default() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+/// Interned vertex positions, keyed by `PtId`.
+pub(crate) struct VertexIndex<S: Scalar> {
+    points: BTreeMap<PtId, Vector3<S>>,
+}
+
+impl<S: Scalar> VertexIndex<S> {
+    pub(crate) fn new() -> Self {
+        Self {
+            points: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn get_point(&self, id: PtId) -> Vector3<S> {
+        *self
+            .points
+            .get(&id)
+            .unwrap_or_else(|| panic!("No point found: {id:?}"))
+    }
+
+    pub(crate) fn set_point(&mut self, id: PtId, p: Vector3<S>) {
+        self.points.insert(id, p);
+    }
+
+    /// Intern `p`, returning the existing `PtId` if an exactly equal point
+    /// is already present rather than allocating a duplicate.
+    pub(crate) fn get_or_insert_point(&mut self, p: Vector3<S>) -> PtId {
+        if let Some((id, _)) = self.points.iter().find(|(_, v)| **v == p) {
+            return *id;
+        }
+        let id = PtId::default();
+        self.points.insert(id, p);
+        id
+    }
+
+    /// All interned points keyed by id, e.g. for serializing out the
+    /// authoritative vertex data.
+    pub(crate) fn points_by_id(&self) -> BTreeMap<PtId, Vector3<S>> {
+        self.points.clone()
+    }
+
+    /// Rebuild a `VertexIndex` from already-assigned ids, e.g. when loading
+    /// a persisted `GeoIndex` back from its wire form.
+    pub(crate) fn from_points(points: BTreeMap<PtId, Vector3<S>>) -> Self {
+        Self { points }
+    }
+}